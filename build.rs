@@ -0,0 +1,45 @@
+//! On Windows, compiles the `rokit-trampoline` shim (see
+//! `crates/rokit-trampoline`) so its bytes can be embedded into tool
+//! aliases, instead of copying the whole Rokit binary into every
+//! alias. Not needed on unix, where aliases are symlinks instead.
+//!
+//! `rokit-trampoline` lives in its own workspace member crate (with no
+//! build script of its own) specifically so that building it here, by
+//! package name, can't recursively trigger this very build script the
+//! way building it as a bin target of this package would.
+//!
+//! NOTE: this relies on `crates/rokit-trampoline` being listed under
+//! the root `Cargo.toml`'s `[workspace.members]` - without that,
+//! `cargo build --package rokit-trampoline` below can't find it.
+
+use std::{env, path::PathBuf, process::Command};
+
+fn main() {
+    println!("cargo:rerun-if-changed=crates/rokit-trampoline/src/main.rs");
+
+    if env::var("CARGO_CFG_WINDOWS").is_err() {
+        return;
+    }
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let trampoline_target_dir = out_dir.join("rokit-trampoline-build");
+
+    let status = Command::new(env::var("CARGO").unwrap())
+        .args(["build", "--package", "rokit-trampoline", "--release"])
+        .arg("--target-dir")
+        .arg(&trampoline_target_dir)
+        .status()
+        .expect("failed to invoke cargo to build rokit-trampoline");
+    assert!(status.success(), "failed to build rokit-trampoline");
+
+    let built_path = trampoline_target_dir
+        .join("release")
+        .join("rokit-trampoline.exe");
+    let embedded_path = out_dir.join("rokit-trampoline.exe");
+    std::fs::copy(&built_path, &embedded_path).expect("failed to copy built rokit-trampoline");
+
+    println!(
+        "cargo:rustc-env=ROKIT_TRAMPOLINE_PATH={}",
+        embedded_path.display()
+    );
+}
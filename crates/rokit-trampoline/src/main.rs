@@ -0,0 +1,103 @@
+//! A minimal trampoline installed as the content of tool aliases on
+//! platforms where a symlink or hardlink to the real Rokit binary
+//! isn't used (namely Windows). Its only job is to locate the real
+//! Rokit executable at the time it's run and re-exec it with the
+//! original `arg0` and arguments preserved, so that:
+//!
+//! - Adding a tool alias never duplicates the Rokit binary's bytes.
+//! - Updating Rokit never requires rewriting any alias, since none
+//!   of them embed Rokit itself anymore, only this shim.
+//! - Re-exec'd and recursive tool-to-tool invocations keep resolving
+//!   through the shim, rather than through a path captured once at
+//!   alias-creation time that could go stale after a reinstall.
+
+use std::{
+    env::{args_os, var_os},
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::{exit, Command},
+};
+
+const ROKIT_HOME_VAR: &str = "ROKIT_HOME";
+
+fn main() {
+    let args: Vec<OsString> = args_os().collect();
+
+    let Some(real_exe) = locate_real_exe() else {
+        eprintln!("rokit-trampoline: could not locate the real Rokit executable");
+        exit(1);
+    };
+
+    run(&real_exe, &args);
+}
+
+/**
+    Finds the real Rokit executable, re-resolving the home directory
+    on every invocation instead of relying on a path baked in at
+    alias-creation time.
+*/
+fn locate_real_exe() -> Option<PathBuf> {
+    let home = match var_os(ROKIT_HOME_VAR) {
+        Some(path) => PathBuf::from(path),
+        None => dirs::home_dir()?.join(".rokit"),
+    };
+
+    // The same file name that Rokit's tool storage installs its own binary under.
+    let candidate = home
+        .join("bin")
+        .join(format!("aftman{}", std::env::consts::EXE_SUFFIX));
+    candidate.exists().then_some(candidate)
+}
+
+/**
+    Runs the real Rokit executable with the original `arg0` and the
+    rest of the arguments preserved, then exits with its exit code.
+
+    On unix this replaces the current process image entirely via
+    `exec`, so there's no trampoline process left running. On other
+    platforms we spawn and wait instead, since there's no portable
+    way to replace the current process, and forward the original
+    `arg0` through an environment variable instead of the process's
+    argv\[0\].
+*/
+fn run(real_exe: &Path, args: &[OsString]) -> ! {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        let mut command = Command::new(real_exe);
+        if let Some(arg0) = args.first() {
+            command.arg0(arg0);
+        }
+        if args.len() > 1 {
+            command.args(&args[1..]);
+        }
+
+        let err = command.exec();
+        eprintln!(
+            "rokit-trampoline: failed to exec {}: {err}",
+            real_exe.display()
+        );
+        exit(1);
+    }
+
+    #[cfg(not(unix))]
+    {
+        let arg0 = args.first().cloned().unwrap_or_default();
+        let status = Command::new(real_exe)
+            .env("ROKIT_TRAMPOLINE_ARG0", arg0)
+            .args(args.iter().skip(1))
+            .status();
+
+        match status {
+            Ok(status) => exit(status.code().unwrap_or(1)),
+            Err(err) => {
+                eprintln!(
+                    "rokit-trampoline: failed to spawn {}: {err}",
+                    real_exe.display()
+                );
+                exit(1);
+            }
+        }
+    }
+}
@@ -1,23 +1,155 @@
 use std::{
+    collections::HashMap,
     env::{consts::EXE_SUFFIX, current_exe},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
+use async_trait::async_trait;
 use futures::{stream::FuturesUnordered, TryStreamExt};
-use tokio::{
-    fs::{create_dir_all, read, read_dir},
-    sync::Mutex as AsyncMutex,
-    task::spawn_blocking,
-};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{sync::Mutex as AsyncMutex, task::spawn_blocking};
 use tracing::debug;
 
 use crate::{
-    result::AftmanResult,
+    result::{AftmanError, AftmanResult},
     tool::{ToolAlias, ToolSpec},
     util::{write_executable_file, write_executable_link},
 };
 
+/**
+    An abstraction over the filesystem operations `ToolStorage` needs.
+
+    This exists so that link recreation, content-addressing, and the
+    various error paths around them can be tested deterministically
+    with an in-memory implementation, without touching a real (and
+    platform-specific) scratch directory.
+*/
+#[async_trait]
+pub(crate) trait Fs: std::fmt::Debug + Send + Sync {
+    async fn create_dir_all(&self, path: &Path) -> AftmanResult<()>;
+    async fn exists(&self, path: &Path) -> bool;
+    /// Whether links created with [`Fs::write_executable_link`] are real
+    /// symlinks, resolved at the time the alias is run rather than the
+    /// time it's created. Determines whether aliases can track the
+    /// Aftman binary's own hash, or need to track the content-addressed
+    /// blob they're hardlinked to instead.
+    fn supports_symlinks(&self) -> bool;
+    async fn read(&self, path: &Path) -> AftmanResult<Vec<u8>>;
+    async fn read_dir(&self, path: &Path) -> AftmanResult<Vec<PathBuf>>;
+    async fn write(&self, path: &Path, contents: &[u8]) -> AftmanResult<()>;
+    async fn write_executable_file(&self, path: &Path, contents: &[u8]) -> AftmanResult<()>;
+    async fn write_executable_link(&self, path: &Path, target: &Path) -> AftmanResult<()>;
+    async fn hard_link(&self, src: &Path, dest: &Path) -> AftmanResult<()>;
+    async fn copy(&self, src: &Path, dest: &Path) -> AftmanResult<()>;
+    async fn remove_file(&self, path: &Path) -> AftmanResult<()>;
+}
+
+/**
+    The real, disk-backed implementation of [`Fs`], wrapping `tokio::fs`
+    and the platform-specific executable writers from `crate::util`.
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RealFs;
+
+#[async_trait]
+impl Fs for RealFs {
+    async fn create_dir_all(&self, path: &Path) -> AftmanResult<()> {
+        tokio::fs::create_dir_all(path).await?;
+        Ok(())
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        tokio::fs::try_exists(path).await.unwrap_or(false)
+    }
+
+    fn supports_symlinks(&self) -> bool {
+        cfg!(unix)
+    }
+
+    async fn read(&self, path: &Path) -> AftmanResult<Vec<u8>> {
+        Ok(tokio::fs::read(path).await?)
+    }
+
+    async fn read_dir(&self, path: &Path) -> AftmanResult<Vec<PathBuf>> {
+        let mut reader = tokio::fs::read_dir(path).await?;
+        let mut paths = Vec::new();
+        while let Some(entry) = reader.next_entry().await? {
+            paths.push(entry.path());
+        }
+        Ok(paths)
+    }
+
+    async fn write(&self, path: &Path, contents: &[u8]) -> AftmanResult<()> {
+        tokio::fs::write(path, contents).await?;
+        Ok(())
+    }
+
+    async fn write_executable_file(&self, path: &Path, contents: &[u8]) -> AftmanResult<()> {
+        write_executable_file(path, contents).await
+    }
+
+    async fn write_executable_link(&self, path: &Path, target: &Path) -> AftmanResult<()> {
+        write_executable_link(path, target).await
+    }
+
+    async fn hard_link(&self, src: &Path, dest: &Path) -> AftmanResult<()> {
+        tokio::fs::hard_link(src, dest).await?;
+        Ok(())
+    }
+
+    async fn copy(&self, src: &Path, dest: &Path) -> AftmanResult<()> {
+        tokio::fs::copy(src, dest).await?;
+        Ok(())
+    }
+
+    async fn remove_file(&self, path: &Path) -> AftmanResult<()> {
+        tokio::fs::remove_file(path).await?;
+        Ok(())
+    }
+}
+
+/// The bytes of the `rokit-trampoline` shim, compiled by `build.rs`.
+/// Used as tool alias content on platforms that don't symlink aliases
+/// to the Rokit binary, so that adding an alias or updating Rokit
+/// never requires duplicating or rewriting the full executable.
+#[cfg(windows)]
+static TRAMPOLINE_BYTES: &[u8] = include_bytes!(env!("ROKIT_TRAMPOLINE_PATH"));
+
+/**
+    On-disk record of which content hash each alias, as well as the
+    Aftman binary itself, currently points to.
+
+    This lets `recreate_all_links` skip rewriting links that already
+    point at the right content instead of unconditionally rewriting
+    every link on every run.
+*/
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LinkManifest {
+    aftman_hash: String,
+    #[serde(default)]
+    alias_hashes: HashMap<String, String>,
+}
+
+impl LinkManifest {
+    async fn load(fs: &dyn Fs, path: &Path) -> Self {
+        match fs.read(path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    async fn save(&self, fs: &dyn Fs, path: &Path) -> AftmanResult<()> {
+        let bytes = serde_json::to_vec_pretty(self)?;
+        fs.write(path, &bytes).await?;
+        Ok(())
+    }
+}
+
 /**
     Storage for tool binaries and aliases.
 
@@ -28,8 +160,13 @@ use crate::{
 pub struct ToolStorage {
     pub(super) tools_dir: Arc<Path>,
     pub(super) aliases_dir: Arc<Path>,
+    blobs_dir: Arc<Path>,
+    links_manifest_path: Arc<Path>,
+    links_manifest: Arc<AsyncMutex<LinkManifest>>,
+    links_dirty: Arc<AtomicBool>,
     current_exe_path: Arc<Path>,
     current_exe_contents: Arc<AsyncMutex<Option<Vec<u8>>>>,
+    fs: Arc<dyn Fs>,
 }
 
 impl ToolStorage {
@@ -52,11 +189,119 @@ impl ToolStorage {
         if let Some(contents) = &*guard {
             return Ok(contents.clone());
         }
-        let contents = read(&self.current_exe_path).await?;
+        let contents = self.fs.read(&self.current_exe_path).await?;
         *guard = Some(contents.clone());
         Ok(contents)
     }
 
+    fn blob_path(&self, hash: &str) -> PathBuf {
+        self.blobs_dir.join(hash)
+    }
+
+    fn hash_contents(contents: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(contents);
+        format!("{:x}", hasher.finalize())
+    }
+
+    /**
+        Returns the bytes that should be written as the content of a
+        tool alias, given the current Rokit binary's contents.
+
+        On platforms where aliases can't be symlinked to the Rokit
+        binary, this is the tiny `rokit-trampoline` shim instead of
+        the full binary, so aliases never need rewriting when Rokit
+        itself is updated.
+    */
+    fn alias_contents(rokit_contents: &[u8]) -> &[u8] {
+        #[cfg(windows)]
+        {
+            let _ = rokit_contents;
+            TRAMPOLINE_BYTES
+        }
+        #[cfg(not(windows))]
+        {
+            rokit_contents
+        }
+    }
+
+    /**
+        Stores the given contents under the content-addressed blob
+        directory, keyed by the SHA-256 hash of those contents.
+
+        If a blob with the same hash already exists, it is assumed to
+        hold identical bytes and is left untouched, so storing the
+        same tool binary or Aftman executable twice is effectively free -
+        the existence check alone is cheap, independent of the blob's size.
+
+        Returns the hash and the path of the stored blob.
+    */
+    async fn store_blob(&self, contents: &[u8]) -> AftmanResult<(String, PathBuf)> {
+        let hash = Self::hash_contents(contents);
+        let path = self.blob_path(&hash);
+        if !self.fs.exists(&path).await {
+            self.fs.create_dir_all(&self.blobs_dir).await?;
+            self.fs.write_executable_file(&path, contents).await?;
+        }
+        Ok((hash, path))
+    }
+
+    /**
+        Links the given destination path to the given blob.
+
+        Prefers a hardlink so that identical tool binaries and aliases
+        never duplicate bytes on disk, falling back to a full copy on
+        filesystems where hardlinks are not supported, such as when the
+        blob store and the destination live on different volumes.
+
+        If a file already exists at the destination it is removed first,
+        since creating a hardlink on top of an existing file would fail.
+    */
+    async fn link_to_blob(
+        &self,
+        dest: impl AsRef<Path>,
+        hash: &str,
+        blob_path: impl AsRef<Path>,
+    ) -> AftmanResult<()> {
+        let dest = dest.as_ref();
+        let blob_path = blob_path.as_ref();
+
+        let _ = self.fs.remove_file(dest).await;
+        if self.fs.hard_link(blob_path, dest).await.is_err() {
+            self.fs.copy(blob_path, dest).await?;
+        }
+
+        self.record_blob_reference(hash, dest).await?;
+
+        Ok(())
+    }
+
+    /**
+        Records that `referenced_by` now points at the blob with the
+        given hash, by appending to a small sidecar file kept alongside
+        the blob in the blob store.
+
+        This does not prune anything itself, but means a future
+        garbage-collection pass can tell which blobs are still
+        referenced by reading `blobs/<hash>.refs` rather than having to
+        walk the entire tool-storage and alias directory trees.
+    */
+    async fn record_blob_reference(&self, hash: &str, referenced_by: &Path) -> AftmanResult<()> {
+        let refs_path = self.blobs_dir.join(format!("{hash}.refs"));
+        let mut existing = self.fs.read(&refs_path).await.unwrap_or_default();
+
+        let line = format!("{}\n", referenced_by.display());
+        if !existing
+            .windows(line.len())
+            .any(|window| window == line.as_bytes())
+        {
+            existing.extend_from_slice(line.as_bytes());
+            self.fs.write(&refs_path, &existing).await?;
+        }
+
+        Ok(())
+    }
+
     /**
         Returns the path to the binary for the given tool.
 
@@ -68,6 +313,10 @@ impl ToolStorage {
 
     /**
         Replaces the binary contents for the given tool.
+
+        The contents are written once into the content-addressed blob
+        store, and the versioned tool path is hardlinked (or copied, if
+        hardlinks are unsupported) to that blob.
     */
     pub async fn replace_tool_contents(
         &self,
@@ -75,8 +324,9 @@ impl ToolStorage {
         contents: impl AsRef<[u8]>,
     ) -> AftmanResult<()> {
         let (dir_path, file_path) = self.tool_paths(spec);
-        create_dir_all(dir_path).await?;
-        write_executable_file(&file_path, contents).await?;
+        self.fs.create_dir_all(&dir_path).await?;
+        let (hash, blob_path) = self.store_blob(contents.as_ref()).await?;
+        self.link_to_blob(&file_path, &hash, &blob_path).await?;
         Ok(())
     }
 
@@ -100,7 +350,9 @@ impl ToolStorage {
             }
             None => self.aftman_contents().await?,
         };
-        write_executable_file(self.aftman_path(), &contents).await?;
+        self.fs
+            .write_executable_file(&self.aftman_path(), &contents)
+            .await?;
         Ok(())
     }
 
@@ -112,7 +364,8 @@ impl ToolStorage {
     pub async fn create_tool_link(&self, alias: &ToolAlias) -> AftmanResult<()> {
         let path = self.aliases_dir.join(alias.name());
         let contents = self.aftman_contents().await?;
-        write_executable_file(path, &contents).await?;
+        let (hash, blob_path) = self.store_blob(Self::alias_contents(&contents)).await?;
+        self.link_to_blob(&path, &hash, &blob_path).await?;
         Ok(())
     }
 
@@ -130,12 +383,11 @@ impl ToolStorage {
     pub async fn recreate_all_links(&self) -> AftmanResult<(bool, bool)> {
         let contents = self.aftman_contents().await?;
         let aftman_path = self.aftman_path();
-        let mut aftman_found = false;
+        let current_hash = Self::hash_contents(&contents);
 
+        let mut aftman_found = false;
         let mut link_paths = Vec::new();
-        let mut link_reader = read_dir(&self.aliases_dir).await?;
-        while let Some(entry) = link_reader.next_entry().await? {
-            let path = entry.path();
+        for path in self.fs.read_dir(&self.aliases_dir).await? {
             if path != aftman_path {
                 debug!(?path, "Found existing link");
                 link_paths.push(path);
@@ -144,57 +396,411 @@ impl ToolStorage {
             }
         }
 
-        // Always write the Aftman binary to ensure it's up-to-date
-        let existing_aftman_binary = read(&aftman_path).await.unwrap_or_default();
-        let was_aftman_updated = existing_aftman_binary != contents;
-        write_executable_file(&aftman_path, &contents).await?;
+        let mut manifest = self.links_manifest.lock().await;
+        let was_aftman_updated = !aftman_found || manifest.aftman_hash != current_hash;
 
-        // Then we can write the rest of the links - on unix we can use
-        // symlinks pointing to the aftman binary to save on disk space.
-        link_paths
+        if was_aftman_updated {
+            self.fs
+                .write_executable_file(&aftman_path, &contents)
+                .await?;
+            manifest.aftman_hash.clone_from(&current_hash);
+            self.links_dirty.store(true, Ordering::Relaxed);
+        }
+
+        // Store the alias content (the trampoline shim where supported,
+        // otherwise the full Aftman binary) as a blob once, so that every
+        // alias that falls back to this below (platforms without symlink
+        // support) links against it instead of rewriting the bytes.
+        let (aftman_hash, aftman_blob_path) =
+            self.store_blob(Self::alias_contents(&contents)).await?;
+
+        // Where symlinks are supported, a link is a symlink pointing at the
+        // aftman binary, so its up-to-dateness tracks the Aftman binary's own
+        // hash; everywhere else it's a hardlink to the alias-content blob
+        // (the trampoline shim where supported), so it tracks that blob's
+        // hash instead.
+        let supports_symlinks = self.fs.supports_symlinks();
+        let link_target_hash = if supports_symlinks {
+            &current_hash
+        } else {
+            &aftman_hash
+        };
+
+        // Then we can write the rest of the links - where symlinks are
+        // supported we use one pointing at the aftman binary to save on
+        // disk space, and everywhere else we hardlink to the
+        // content-addressed blob. Links whose recorded hash in the
+        // manifest already matches the current target are skipped
+        // entirely, since their bytes can't differ.
+        let updated_aliases = link_paths
             .into_iter()
-            .map(|link_path| async {
-                if cfg!(unix) {
-                    write_executable_link(link_path, &aftman_path).await
+            .filter_map(|link_path| {
+                let Some(name) = link_path.file_name().and_then(|name| name.to_str()) else {
+                    // A non-UTF-8 alias name can't be looked up in (or recorded
+                    // into) the manifest, so we can't know it's up to date -
+                    // treat it as stale and rewrite it every time instead of
+                    // silently dropping it from `updated_aliases` forever.
+                    debug!(
+                        ?link_path,
+                        "Alias name is not valid UTF-8, treating as stale"
+                    );
+                    return Some((link_path, None));
+                };
+                let name = name.to_owned();
+                let up_to_date = manifest
+                    .alias_hashes
+                    .get(&name)
+                    .is_some_and(|hash| hash == link_target_hash);
+                if up_to_date {
+                    debug!(?link_path, "Link is up to date, skipping");
+                    None
+                } else {
+                    Some((link_path, Some(name)))
+                }
+            })
+            .map(|(link_path, name)| async {
+                if supports_symlinks {
+                    self.fs
+                        .write_executable_link(&link_path, &aftman_path)
+                        .await?;
                 } else {
-                    write_executable_file(link_path, &contents).await
+                    self.link_to_blob(&link_path, &aftman_hash, &aftman_blob_path)
+                        .await?;
                 }
+                Ok::<_, AftmanError>(name)
             })
             .collect::<FuturesUnordered<_>>()
             .try_collect::<Vec<_>>()
             .await?;
 
+        if !updated_aliases.is_empty() {
+            for name in updated_aliases.into_iter().flatten() {
+                manifest.alias_hashes.insert(name, link_target_hash.clone());
+            }
+            self.links_dirty.store(true, Ordering::Relaxed);
+        }
+
         Ok((aftman_found, was_aftman_updated))
     }
 
+    /**
+        Flushes the link-state manifest to disk if anything has
+        changed since it was last saved.
+
+        This is a no-op when [`ToolStorage::needs_saving`] would
+        return `false`, so callers such as `self-install` can call
+        this unconditionally after syncing links.
+    */
+    pub async fn flush_links(&self) -> AftmanResult<()> {
+        if self.links_dirty.load(Ordering::Relaxed) {
+            let manifest = self.links_manifest.lock().await;
+            manifest.save(&*self.fs, &self.links_manifest_path).await?;
+            self.links_dirty.store(false, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
     pub(crate) async fn load(home_path: impl AsRef<Path>) -> AftmanResult<Self> {
         let home_path = home_path.as_ref();
 
-        let tools_dir = home_path.join("tool-storage").into();
-        let aliases_dir = home_path.join("bin").into();
-
         let (_, _, current_exe_res) = tokio::try_join!(
-            create_dir_all(&tools_dir),
-            create_dir_all(&aliases_dir),
+            tokio::fs::create_dir_all(home_path.join("tool-storage")),
+            tokio::fs::create_dir_all(home_path.join("bin")),
             // NOTE: A call to current_exe is blocking on some
             // platforms, so we spawn it in a blocking task here.
             async { Ok(spawn_blocking(current_exe).await?) },
         )?;
 
-        let current_exe_path = current_exe_res?.into();
-        let current_exe_contents = Arc::new(AsyncMutex::new(None));
+        Self::with_fs(home_path, current_exe_res?, Arc::new(RealFs)).await
+    }
+
+    /**
+        Constructs a `ToolStorage` rooted at `home_path`, using the
+        given executable path and [`Fs`] implementation.
+
+        This is the constructor `load` delegates to once the real
+        filesystem directories exist and the current executable has
+        been located, and is also what tests use to build a
+        `ToolStorage` backed by an in-memory `Fs` instead.
+    */
+    async fn with_fs(
+        home_path: &Path,
+        current_exe_path: PathBuf,
+        fs: Arc<dyn Fs>,
+    ) -> AftmanResult<Self> {
+        let tools_dir = home_path.join("tool-storage").into();
+        let aliases_dir = home_path.join("bin").into();
+        let blobs_dir = home_path.join("blobs").into();
+        let links_manifest_path: Arc<Path> = home_path.join("links.json").into();
+
+        let links_manifest = LinkManifest::load(&*fs, &links_manifest_path).await;
 
         Ok(Self {
-            current_exe_path,
-            current_exe_contents,
+            current_exe_path: current_exe_path.into(),
+            current_exe_contents: Arc::new(AsyncMutex::new(None)),
             tools_dir,
             aliases_dir,
+            blobs_dir,
+            links_manifest_path,
+            links_manifest: Arc::new(AsyncMutex::new(links_manifest)),
+            links_dirty: Arc::new(AtomicBool::new(false)),
+            fs,
         })
     }
 
-    pub(crate) fn needs_saving(&self) -> bool {
-        // Tool storage always writes all state directly
-        // to the disk, but this may change in the future
-        false
+    pub fn needs_saving(&self) -> bool {
+        self.links_dirty.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap as StdHashMap, sync::Mutex as StdMutex};
+
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    enum FakeEntry {
+        File(Vec<u8>),
+        Link(PathBuf),
+    }
+
+    /**
+        An in-memory [`Fs`] implementation for tests, so the branching
+        logic in `recreate_all_links` - symlinks where supported,
+        hardlink with a copy fallback elsewhere - can be exercised
+        without a real, platform-specific scratch directory.
+
+        Whether symlinks are "supported" is a settable flag rather than
+        tied to the host OS, so both branches can be exercised on any
+        platform the tests happen to run on.
+    */
+    #[derive(Debug)]
+    struct FakeFs {
+        entries: StdMutex<StdHashMap<PathBuf, FakeEntry>>,
+        hard_link_fails: StdMutex<bool>,
+        symlinks_supported: StdMutex<bool>,
+        link_writes: StdMutex<u32>,
+    }
+
+    impl Default for FakeFs {
+        fn default() -> Self {
+            Self {
+                entries: StdMutex::new(StdHashMap::new()),
+                hard_link_fails: StdMutex::new(false),
+                symlinks_supported: StdMutex::new(true),
+                link_writes: StdMutex::new(0),
+            }
+        }
+    }
+
+    impl FakeFs {
+        fn resolve<'a>(
+            entries: &'a StdHashMap<PathBuf, FakeEntry>,
+            mut path: &'a Path,
+        ) -> Option<&'a Vec<u8>> {
+            loop {
+                match entries.get(path)? {
+                    FakeEntry::File(contents) => return Some(contents),
+                    FakeEntry::Link(target) => path = target,
+                }
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Fs for FakeFs {
+        async fn create_dir_all(&self, _path: &Path) -> AftmanResult<()> {
+            Ok(())
+        }
+
+        async fn exists(&self, path: &Path) -> bool {
+            let entries = self.entries.lock().unwrap();
+            Self::resolve(&entries, path).is_some()
+        }
+
+        fn supports_symlinks(&self) -> bool {
+            *self.symlinks_supported.lock().unwrap()
+        }
+
+        async fn read(&self, path: &Path) -> AftmanResult<Vec<u8>> {
+            let entries = self.entries.lock().unwrap();
+            Self::resolve(&entries, path)
+                .cloned()
+                .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound).into())
+        }
+
+        async fn read_dir(&self, path: &Path) -> AftmanResult<Vec<PathBuf>> {
+            let entries = self.entries.lock().unwrap();
+            Ok(entries
+                .keys()
+                .filter(|p| p.parent() == Some(path))
+                .cloned()
+                .collect())
+        }
+
+        async fn write(&self, path: &Path, contents: &[u8]) -> AftmanResult<()> {
+            self.entries
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), FakeEntry::File(contents.to_vec()));
+            Ok(())
+        }
+
+        async fn write_executable_file(&self, path: &Path, contents: &[u8]) -> AftmanResult<()> {
+            self.write(path, contents).await
+        }
+
+        async fn write_executable_link(&self, path: &Path, target: &Path) -> AftmanResult<()> {
+            *self.link_writes.lock().unwrap() += 1;
+            self.entries
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), FakeEntry::Link(target.to_path_buf()));
+            Ok(())
+        }
+
+        async fn hard_link(&self, src: &Path, dest: &Path) -> AftmanResult<()> {
+            if *self.hard_link_fails.lock().unwrap() {
+                return Err(std::io::Error::from(std::io::ErrorKind::Unsupported).into());
+            }
+            self.write_executable_link(dest, src).await
+        }
+
+        async fn copy(&self, src: &Path, dest: &Path) -> AftmanResult<()> {
+            let contents = self.read(src).await?;
+            self.write(dest, &contents).await
+        }
+
+        async fn remove_file(&self, path: &Path) -> AftmanResult<()> {
+            self.entries.lock().unwrap().remove(path);
+            Ok(())
+        }
+    }
+
+    async fn storage_with(fs: Arc<FakeFs>) -> ToolStorage {
+        ToolStorage::with_fs(Path::new("/home"), PathBuf::from("/home/current-exe"), fs)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn link_to_blob_hard_links_by_default() {
+        let fs = Arc::new(FakeFs::default());
+        fs.write(Path::new("/home/current-exe"), b"rokit-binary")
+            .await
+            .unwrap();
+
+        let storage = storage_with(fs.clone()).await;
+        storage
+            .link_to_blob(
+                Path::new("/home/bin/my-tool"),
+                "placeholder",
+                Path::new("/home/current-exe"),
+            )
+            .await
+            .unwrap();
+
+        let entries = fs.entries.lock().unwrap();
+        assert!(matches!(
+            entries.get(Path::new("/home/bin/my-tool")),
+            Some(FakeEntry::Link(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn recreate_all_links_symlinks_aliases_when_supported() {
+        let fs = Arc::new(FakeFs::default());
+        fs.write(Path::new("/home/current-exe"), b"rokit-binary")
+            .await
+            .unwrap();
+        fs.write_executable_link(Path::new("/home/bin/my-tool"), Path::new("/home/stale"))
+            .await
+            .unwrap();
+
+        let storage = storage_with(fs.clone()).await;
+
+        let (aftman_found, aftman_updated) = storage.recreate_all_links().await.unwrap();
+        assert!(!aftman_found);
+        assert!(aftman_updated);
+        storage.flush_links().await.unwrap();
+
+        let aftman_path = storage.aftman_path();
+        assert!(matches!(
+            fs.entries.lock().unwrap().get(Path::new("/home/bin/my-tool")),
+            Some(FakeEntry::Link(target)) if *target == aftman_path
+        ));
+
+        // Nothing should be rewritten on a second pass, since both the
+        // Aftman binary and the alias already match what's recorded.
+        *fs.link_writes.lock().unwrap() = 0;
+        let (aftman_found, aftman_updated) = storage.recreate_all_links().await.unwrap();
+        assert!(aftman_found);
+        assert!(!aftman_updated);
+        assert_eq!(*fs.link_writes.lock().unwrap(), 0);
+        assert!(!storage.needs_saving());
+    }
+
+    #[tokio::test]
+    async fn recreate_all_links_falls_back_to_blob_hardlinks_when_symlinks_unsupported() {
+        let fs = Arc::new(FakeFs::default());
+        *fs.symlinks_supported.lock().unwrap() = false;
+        fs.write(Path::new("/home/current-exe"), b"rokit-binary")
+            .await
+            .unwrap();
+        fs.write_executable_link(Path::new("/home/bin/my-tool"), Path::new("/home/stale"))
+            .await
+            .unwrap();
+
+        let storage = storage_with(fs.clone()).await;
+        storage.recreate_all_links().await.unwrap();
+        storage.flush_links().await.unwrap();
+
+        let expected_blob = storage.blob_path(&ToolStorage::hash_contents(
+            ToolStorage::alias_contents(b"rokit-binary"),
+        ));
+        assert!(matches!(
+            fs.entries.lock().unwrap().get(Path::new("/home/bin/my-tool")),
+            Some(FakeEntry::Link(target)) if *target == expected_blob
+        ));
+
+        // Nothing should be rewritten on a second pass, since the alias
+        // already matches the blob it's recorded as pointing to.
+        *fs.link_writes.lock().unwrap() = 0;
+        storage.recreate_all_links().await.unwrap();
+        assert_eq!(*fs.link_writes.lock().unwrap(), 0);
+        assert!(!storage.needs_saving());
+    }
+
+    #[tokio::test]
+    async fn link_to_blob_falls_back_to_copy_when_hard_link_unsupported() {
+        let fs = Arc::new(FakeFs::default());
+        fs.write(Path::new("/home/blob"), b"contents")
+            .await
+            .unwrap();
+        *fs.hard_link_fails.lock().unwrap() = true;
+
+        let storage = storage_with(fs.clone()).await;
+        storage
+            .link_to_blob(Path::new("/home/bin/tool"), "hash", Path::new("/home/blob"))
+            .await
+            .unwrap();
+
+        let entries = fs.entries.lock().unwrap();
+        assert!(matches!(
+            entries.get(Path::new("/home/bin/tool")),
+            Some(FakeEntry::File(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn needs_saving_is_false_until_links_are_mutated() {
+        let fs = Arc::new(FakeFs::default());
+        fs.write(Path::new("/home/current-exe"), b"rokit-binary")
+            .await
+            .unwrap();
+
+        let storage = storage_with(fs).await;
+        assert!(!storage.needs_saving());
     }
 }
@@ -1,9 +1,14 @@
-use std::{env::var, path::MAIN_SEPARATOR_STR};
+use std::{
+    env::{args_os, var},
+    path::{PathBuf, MAIN_SEPARATOR_STR},
+};
 
 use crate::{result::RokitResult, storage::Home};
 
 mod shell;
 
+pub use shell::Shell;
+
 #[cfg(unix)]
 mod unix;
 
@@ -11,11 +16,16 @@ mod unix;
 mod windows;
 
 /**
-    Tries to add the Rokit binaries directory to the system PATH.
+    Tries to add the Rokit binaries directory to the PATH for every
+    shell Rokit can detect and write a profile snippet for - this may
+    be fish, nushell, zsh, bash, sh, or PowerShell, depending on the
+    platform and the user's environment.
 
-    Returns `true` if the directory was added to the PATH, `false` otherwise.
+    Returns the list of shells that were newly updated. An empty list
+    means the binaries directory was already present everywhere Rokit
+    could find, or that no supported shell could be detected.
 */
-pub async fn add_to_path(home: &Home) -> RokitResult<bool> {
+pub async fn add_to_path(home: &Home) -> RokitResult<Vec<Shell>> {
     #[cfg(unix)]
     {
         self::unix::add_to_path(home).await
@@ -27,13 +37,50 @@ pub async fn add_to_path(home: &Home) -> RokitResult<bool> {
 }
 
 /**
-    Checks if the Rokit binaries directory is in the system PATH.
+    Checks if the Rokit binaries directory is in the system PATH, or
+    in one of the shell-specific config files Rokit knows how to
+    write to.
 
     Returns `true` if the directory is in the PATH, `false` otherwise.
 */
-pub fn exists_in_path(_home: &Home) -> bool {
+pub fn exists_in_path(home: &Home) -> bool {
     let pattern = format!("rokit{MAIN_SEPARATOR_STR}bin");
-    var("PATH")
-        .map(|path| path.split(':').any(|item| item.ends_with(&pattern)))
-        .unwrap_or(false)
+    let separator = if cfg!(windows) { ';' } else { ':' };
+
+    let in_path_env = var("PATH")
+        .map(|path| path.split(separator).any(|item| item.ends_with(&pattern)))
+        .unwrap_or(false);
+
+    in_path_env
+        || shell::detect_shells()
+            .into_iter()
+            .any(|shell| shell::snippet_exists(shell, home))
+}
+
+/**
+    Determines the name of the tool alias the current process was
+    invoked as, for the Rokit entrypoint to use when deciding whether
+    it's running as itself or as a tool alias.
+
+    Prefers the original `arg0` forwarded through the `ROKIT_TRAMPOLINE_ARG0`
+    environment variable by the `rokit-trampoline` shim, which is used as
+    alias content on platforms (namely Windows) where `std::process::Command`
+    can't set a spawned child's own argv\[0\], before falling back to the
+    file stem of this process's own argv\[0\] - which is what happens when Rokit is
+    invoked directly, or through a unix symlink alias that already preserves
+    argv\[0\] on its own.
+
+    NOTE: the entrypoint's alias dispatch needs to call this instead of
+    resolving `current_exe()` itself for `ROKIT_TRAMPOLINE_ARG0` to have
+    any effect.
+*/
+pub fn invoked_alias_name() -> Option<String> {
+    let arg0 = var("ROKIT_TRAMPOLINE_ARG0")
+        .ok()
+        .filter(|arg0| !arg0.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| args_os().next().map(PathBuf::from))?;
+
+    arg0.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
 }
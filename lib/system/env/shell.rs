@@ -0,0 +1,365 @@
+use std::{env::var, path::PathBuf};
+
+use tokio::fs::{create_dir_all, read_to_string, write};
+
+use crate::{result::RokitResult, storage::Home};
+
+const SNIPPET_BEGIN: &str = "# >>> rokit path >>>";
+const SNIPPET_END: &str = "# <<< rokit path <<<";
+
+/**
+    A shell that Rokit knows how to add its binaries directory to
+    the PATH for.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Shell {
+    Fish,
+    NuShell,
+    Zsh,
+    Bash,
+    Sh,
+    PowerShell,
+}
+
+impl Shell {
+    /// A short, human-readable name for this shell.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fish => "fish",
+            Self::NuShell => "nushell",
+            Self::Zsh => "zsh",
+            Self::Bash => "bash",
+            Self::Sh => "sh",
+            Self::PowerShell => "PowerShell",
+        }
+    }
+}
+
+/**
+    Detects which shell config files Rokit should try to write the
+    binaries directory to the PATH for.
+
+    More than one shell may be returned - a user might have zsh as
+    their login shell but also use fish or nushell interactively, and
+    we want to cover any of them whose config directory is present.
+*/
+#[cfg(windows)]
+pub fn detect_shells() -> Vec<Shell> {
+    vec![Shell::PowerShell]
+}
+
+#[cfg(unix)]
+pub fn detect_shells() -> Vec<Shell> {
+    let mut shells = Vec::new();
+
+    if let Some(config_dir) = dirs::config_dir() {
+        if config_dir.join("fish").is_dir() {
+            shells.push(Shell::Fish);
+        }
+        if config_dir.join("nushell").is_dir() {
+            shells.push(Shell::NuShell);
+        }
+    }
+
+    match var("SHELL") {
+        Ok(shell) if shell.contains("zsh") => shells.push(Shell::Zsh),
+        Ok(shell) if shell.contains("bash") => shells.push(Shell::Bash),
+        Ok(shell) if shell.contains("fish") => shells.push(Shell::Fish),
+        _ => shells.push(Shell::Sh),
+    }
+
+    shells.sort();
+    shells.dedup();
+    shells
+}
+
+/**
+    Returns every profile path Rokit should write its PATH snippet to
+    for the given shell.
+
+    This is usually a single path, but Windows PowerShell (5.1, the
+    version every Windows machine ships with by default) and PowerShell
+    7+ (`pwsh`) read their `$PROFILE` from different directories under
+    `Documents`, so both are returned for `Shell::PowerShell` - writing
+    only one would leave the snippet invisible to whichever one the
+    user doesn't happen to have installed.
+*/
+fn profile_paths(shell: Shell) -> Vec<PathBuf> {
+    match shell {
+        Shell::Fish => dirs::config_dir()
+            .map(|dir| dir.join("fish").join("conf.d").join("rokit.fish"))
+            .into_iter()
+            .collect(),
+        Shell::NuShell => dirs::config_dir()
+            .map(|dir| dir.join("nushell").join("env.nu"))
+            .into_iter()
+            .collect(),
+        Shell::Zsh => dirs::home_dir()
+            .map(|dir| dir.join(".zprofile"))
+            .into_iter()
+            .collect(),
+        Shell::Bash => dirs::home_dir()
+            .map(|dir| dir.join(".bashrc"))
+            .into_iter()
+            .collect(),
+        Shell::Sh => dirs::home_dir()
+            .map(|dir| dir.join(".profile"))
+            .into_iter()
+            .collect(),
+        Shell::PowerShell => {
+            let Some(documents) = dirs::document_dir() else {
+                return Vec::new();
+            };
+            vec![
+                documents
+                    .join("WindowsPowerShell")
+                    .join("Microsoft.PowerShell_profile.ps1"),
+                documents
+                    .join("PowerShell")
+                    .join("Microsoft.PowerShell_profile.ps1"),
+            ]
+        }
+    }
+}
+
+fn snippet(shell: Shell, bin_dir: &std::path::Path) -> String {
+    let bin_dir = bin_dir.display();
+    let body = match shell {
+        Shell::Fish => format!("fish_add_path {bin_dir}"),
+        Shell::NuShell => format!("$env.PATH = ($env.PATH | append \"{bin_dir}\")"),
+        Shell::Zsh | Shell::Bash | Shell::Sh => format!("export PATH=\"{bin_dir}:$PATH\""),
+        Shell::PowerShell => format!("$env:Path = \"{bin_dir};$env:Path\""),
+    };
+    format!("{SNIPPET_BEGIN}\n{body}\n{SNIPPET_END}")
+}
+
+/**
+    Writes the PATH snippet for the given shell to every config file it
+    might read its profile from, for any of them where it isn't already
+    present.
+
+    Returns `true` if any config file was written to, `false` if they
+    were all already up to date or if none of the shell's config file
+    locations could be determined.
+*/
+pub(super) async fn write_snippet(shell: Shell, home: &Home) -> RokitResult<bool> {
+    let new_snippet = snippet(shell, &home.path().join("bin"));
+
+    let mut any_written = false;
+    for path in profile_paths(shell) {
+        let existing = read_to_string(&path).await.unwrap_or_default();
+        let Some(contents) = merge_snippet(&existing, &new_snippet) else {
+            continue;
+        };
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).await?;
+        }
+        write(&path, contents).await?;
+        any_written = true;
+    }
+
+    Ok(any_written)
+}
+
+/**
+    Computes the new contents of a shell profile file given its
+    existing contents and the PATH snippet that should be present in
+    it, or `None` if `existing` already contains that exact snippet and
+    nothing needs to change.
+
+    Pulled out of `write_snippet` as a pure function so the
+    fresh/stale/absent branching can be unit tested without touching
+    the filesystem.
+*/
+fn merge_snippet(existing: &str, new_snippet: &str) -> Option<String> {
+    let mut contents = match find_snippet_range(existing) {
+        // The managed block is already there with the right content, nothing to do.
+        Some(range) if existing[range.clone()] == *new_snippet => return None,
+        // The managed block is there but stale (eg. `home` changed), replace it in place.
+        Some(range) => {
+            let mut contents = existing.to_owned();
+            contents.replace_range(range, new_snippet);
+            contents
+        }
+        // No managed block yet, append it to the end of the file.
+        None => {
+            let mut contents = existing.to_owned();
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            contents.push_str(new_snippet);
+            contents
+        }
+    };
+    if !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    Some(contents)
+}
+
+/// Finds the byte range of the managed snippet (markers included) within `contents`, if present.
+fn find_snippet_range(contents: &str) -> Option<std::ops::Range<usize>> {
+    let start = contents.find(SNIPPET_BEGIN)?;
+    let end_marker = contents[start..].find(SNIPPET_END)? + start + SNIPPET_END.len();
+    Some(start..end_marker)
+}
+
+/**
+    Checks whether the managed PATH snippet for the given shell is
+    already present in any of its config files.
+*/
+pub(super) fn snippet_exists(shell: Shell, _home: &Home) -> bool {
+    profile_paths(shell).into_iter().any(|path| {
+        std::fs::read_to_string(path).is_ok_and(|contents| contents.contains(SNIPPET_BEGIN))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    // `detect_shells` reads the process-global `SHELL` env var, so tests
+    // that set it must not run concurrently with each other.
+    static SHELL_ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    const SNIPPET: &str =
+        "# >>> rokit path >>>\nexport PATH=\"/home/user/.rokit/bin:$PATH\"\n# <<< rokit path <<<";
+
+    #[test]
+    fn profile_paths_fish_under_config_dir() {
+        let Some(config_dir) = dirs::config_dir() else {
+            return;
+        };
+        let paths = profile_paths(Shell::Fish);
+        assert_eq!(
+            paths,
+            vec![config_dir.join("fish").join("conf.d").join("rokit.fish")]
+        );
+    }
+
+    #[test]
+    fn profile_paths_nushell_under_config_dir() {
+        let Some(config_dir) = dirs::config_dir() else {
+            return;
+        };
+        let paths = profile_paths(Shell::NuShell);
+        assert_eq!(paths, vec![config_dir.join("nushell").join("env.nu")]);
+    }
+
+    #[test]
+    fn profile_paths_zsh_under_home_dir() {
+        let Some(home_dir) = dirs::home_dir() else {
+            return;
+        };
+        let paths = profile_paths(Shell::Zsh);
+        assert_eq!(paths, vec![home_dir.join(".zprofile")]);
+    }
+
+    #[test]
+    fn profile_paths_bash_under_home_dir() {
+        let Some(home_dir) = dirs::home_dir() else {
+            return;
+        };
+        let paths = profile_paths(Shell::Bash);
+        assert_eq!(paths, vec![home_dir.join(".bashrc")]);
+    }
+
+    #[test]
+    fn profile_paths_sh_under_home_dir() {
+        let Some(home_dir) = dirs::home_dir() else {
+            return;
+        };
+        let paths = profile_paths(Shell::Sh);
+        assert_eq!(paths, vec![home_dir.join(".profile")]);
+    }
+
+    #[test]
+    fn profile_paths_powershell_covers_both_editions() {
+        let Some(documents) = dirs::document_dir() else {
+            return;
+        };
+        let paths = profile_paths(Shell::PowerShell);
+        assert_eq!(
+            paths,
+            vec![
+                documents
+                    .join("WindowsPowerShell")
+                    .join("Microsoft.PowerShell_profile.ps1"),
+                documents
+                    .join("PowerShell")
+                    .join("Microsoft.PowerShell_profile.ps1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_snippet_range_finds_the_managed_block() {
+        let contents = format!("alias foo=bar\n{SNIPPET}\nalias baz=qux\n");
+        let range = find_snippet_range(&contents).expect("snippet should be found");
+        assert_eq!(&contents[range], SNIPPET);
+    }
+
+    #[test]
+    fn find_snippet_range_none_when_absent() {
+        let contents = "alias foo=bar\nalias baz=qux\n";
+        assert_eq!(find_snippet_range(contents), None);
+    }
+
+    #[test]
+    fn merge_snippet_none_when_already_up_to_date() {
+        let existing = format!("alias foo=bar\n{SNIPPET}\n");
+        assert_eq!(merge_snippet(&existing, SNIPPET), None);
+    }
+
+    #[test]
+    fn merge_snippet_replaces_a_stale_block_in_place() {
+        let stale = "# >>> rokit path >>>\nexport PATH=\"/old/path:$PATH\"\n# <<< rokit path <<<";
+        let existing = format!("alias foo=bar\n{stale}\nalias baz=qux\n");
+        let merged = merge_snippet(&existing, SNIPPET).expect("should produce new contents");
+        assert!(merged.contains(SNIPPET));
+        assert!(!merged.contains("/old/path"));
+        assert!(merged.contains("alias foo=bar"));
+        assert!(merged.contains("alias baz=qux"));
+    }
+
+    #[test]
+    fn merge_snippet_appends_when_absent() {
+        let existing = "alias foo=bar\n".to_owned();
+        let merged = merge_snippet(&existing, SNIPPET).expect("should produce new contents");
+        assert!(merged.starts_with("alias foo=bar\n"));
+        assert!(merged.ends_with(&format!("{SNIPPET}\n")));
+    }
+
+    #[test]
+    fn merge_snippet_appends_to_empty_file() {
+        let merged = merge_snippet("", SNIPPET).expect("should produce new contents");
+        assert_eq!(merged, format!("{SNIPPET}\n"));
+    }
+
+    #[test]
+    fn merge_snippet_adds_a_newline_before_appending_if_missing() {
+        let existing = "alias foo=bar".to_owned();
+        let merged = merge_snippet(&existing, SNIPPET).expect("should produce new contents");
+        assert!(merged.starts_with("alias foo=bar\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn detect_shells_matches_zsh_from_shell_env() {
+        let _guard = SHELL_ENV_LOCK.lock().unwrap();
+        std::env::set_var("SHELL", "/bin/zsh");
+        assert!(detect_shells().contains(&Shell::Zsh));
+        std::env::remove_var("SHELL");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn detect_shells_falls_back_to_sh_when_unset() {
+        let _guard = SHELL_ENV_LOCK.lock().unwrap();
+        std::env::remove_var("SHELL");
+        assert!(detect_shells().contains(&Shell::Sh));
+    }
+}
@@ -0,0 +1,19 @@
+use crate::{result::RokitResult, storage::Home};
+
+use super::shell::{self, Shell};
+
+/**
+    Adds the Rokit binaries directory to the PATH for every shell
+    config Rokit could detect for the current user.
+*/
+pub async fn add_to_path(home: &Home) -> RokitResult<Vec<Shell>> {
+    let mut updated = Vec::new();
+
+    for shell in shell::detect_shells() {
+        if shell::write_snippet(shell, home).await? {
+            updated.push(shell);
+        }
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,78 @@
+use std::{ffi::OsStr, os::windows::ffi::OsStrExt, ptr::null_mut};
+
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    SendMessageTimeoutW, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
+};
+use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+use crate::{result::RokitResult, storage::Home};
+
+use super::shell::{self, Shell};
+
+const ENVIRONMENT_KEY: &str = "Environment";
+
+/**
+    Adds the Rokit binaries directory to the current user's `Path`
+    environment variable, and writes a PowerShell `$PROFILE` snippet
+    so new PowerShell sessions pick it up without depending on
+    whichever program happened to last refresh their environment.
+*/
+pub async fn add_to_path(home: &Home) -> RokitResult<Vec<Shell>> {
+    let bin_dir = home.path().join("bin");
+    let bin_dir_str = bin_dir.to_string_lossy().into_owned();
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let env = hkcu.open_subkey_with_flags(ENVIRONMENT_KEY, winreg::enums::KEY_ALL_ACCESS)?;
+    let current_path: String = env.get_value("Path").unwrap_or_default();
+
+    let registry_updated = if current_path
+        .split(';')
+        .any(|entry| entry.eq_ignore_ascii_case(&bin_dir_str))
+    {
+        false
+    } else {
+        let new_path = if current_path.is_empty() {
+            bin_dir_str.clone()
+        } else {
+            format!("{current_path};{bin_dir_str}")
+        };
+        env.set_value("Path", &new_path)?;
+        broadcast_environment_change();
+        true
+    };
+
+    let profile_updated = shell::write_snippet(Shell::PowerShell, home).await?;
+
+    let mut updated = Vec::new();
+    if registry_updated || profile_updated {
+        updated.push(Shell::PowerShell);
+    }
+
+    Ok(updated)
+}
+
+/**
+    Broadcasts a `WM_SETTINGCHANGE` message to all top-level windows so
+    that already-running processes - notably `explorer.exe`, which new
+    terminals typically inherit their initial environment from - pick up
+    the registry `Path` change immediately, instead of only after the
+    next logoff/logon.
+*/
+fn broadcast_environment_change() {
+    let param: Vec<u16> = OsStr::new("Environment")
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    unsafe {
+        SendMessageTimeoutW(
+            HWND_BROADCAST,
+            WM_SETTINGCHANGE,
+            0,
+            param.as_ptr() as isize,
+            SMTO_ABORTIFHUNG,
+            5000,
+            null_mut(),
+        );
+    }
+}
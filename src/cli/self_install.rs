@@ -23,12 +23,18 @@ impl SelfInstallSubcommand {
             "Failed to recreate tool links!\
             \nYour installation may be corrupted.",
         )?;
+        if storage.needs_saving() {
+            storage.flush_links().await.context(
+                "Failed to save link state!\
+                \nYour installation may be corrupted.",
+            )?;
+        }
 
         pb.inc(1);
         pb.set_message("Pathifying");
 
         let mut path_errored = false;
-        let path_was_changed = add_to_path(home)
+        let updated_shells = add_to_path(home)
             .await
             .inspect_err(|e| {
                 path_errored = true;
@@ -38,7 +44,8 @@ impl SelfInstallSubcommand {
                     \nError: {e:?}",
                 )
             })
-            .unwrap_or(false);
+            .unwrap_or_default();
+        let path_was_changed = !updated_shells.is_empty();
         let path_contains_rokit = exists_in_path(home);
 
         // Prompt the user to restart their terminal if:
@@ -46,8 +53,18 @@ impl SelfInstallSubcommand {
         // - PATH does not currently contain Rokit, and adding to PATH did not error
         let should_restart_terminal = path_was_changed || (!path_errored && !path_contains_rokit);
         let should_restart_lines = if should_restart_terminal {
+            let shells_message = if updated_shells.is_empty() {
+                String::new()
+            } else {
+                let names = updated_shells
+                    .iter()
+                    .map(|shell| shell.name())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(" ({names})")
+            };
             format!(
-                "\n\nExecutables for Rokit and tools have been added to {}.\
+                "\n\nExecutables for Rokit and tools have been added to {}{shells_message}.\
                 \nPlease restart your terminal for the changes to take effect.",
                 style("$PATH").bold()
             )